@@ -4,6 +4,70 @@ use alloc::string::ToString;
 use multiversx_sc::errors::SCError;
 use multiversx_sc::imports::*;
 
+/// Empirically-derived upper bound on the gas a single `pong_all` refund costs;
+/// the loop does not start another iteration once the remaining gas is below
+/// this, so it never runs out mid-refund.
+const GAS_PER_PONG_ALL_ITERATION: u64 = 3_000_000;
+
+/// Whether a proposal created at `creation` is still open for voting at `now`.
+fn voting_open(now: u64, creation: u64, period: u64) -> bool {
+    now < creation + period
+}
+
+/// Whether a closed proposal has enough support to execute: strictly more yes
+/// votes than the quorum threshold and than the no votes.
+fn proposal_passes(yes_votes: u64, no_votes: u64, quorum: u64) -> bool {
+    yes_votes > quorum && yes_votes > no_votes
+}
+
+/// Lifecycle state of a single address as seen by the contract.
+#[derive(TopEncode, TopDecode, TypeAbi, PartialEq, Eq, Debug, Clone, Copy)]
+pub enum UserStatus {
+    /// The address has never pinged (or was already refunded).
+    Unknown,
+    /// The address has pinged but its pong deadline has not been reached yet.
+    PingedWaiting,
+    /// The address has pinged and is now eligible to pong.
+    PingedReady,
+}
+
+/// Strategy used to compute the required ping amount.
+#[derive(TopEncode, TopDecode, TypeAbi, PartialEq, Eq, Debug, Clone, Copy)]
+pub enum PricingMode {
+    /// Every ping costs the static `ping_amount`.
+    Fixed,
+    /// The price grows linearly with the number of active pings:
+    /// `base_amount + slope * active_count`.
+    Linear,
+}
+
+/// Authoritative operational status of the contract.
+#[derive(TopEncode, TopDecode, TypeAbi, PartialEq, Eq, Debug, Clone, Copy)]
+pub enum ContractState {
+    Active,
+    Paused,
+}
+
+/// Contract parameter a governance proposal can change.
+#[derive(TopEncode, TopDecode, NestedEncode, NestedDecode, TypeAbi, PartialEq, Eq, Debug, Clone, Copy)]
+pub enum ParameterSetting {
+    PingAmount,
+    DurationInSeconds,
+}
+
+/// A single on-chain proposal to change a contract parameter, with its running
+/// vote tallies and execution state.
+#[derive(TopEncode, TopDecode, NestedEncode, NestedDecode, TypeAbi, Clone)]
+pub struct GovernanceProposal<M: ManagedTypeApi> {
+    pub proposer: ManagedAddress<M>,
+    pub setting: ParameterSetting,
+    pub new_value: BigUint<M>,
+    pub creation_timestamp: u64,
+    pub yes_votes: u64,
+    pub no_votes: u64,
+    pub executed: bool,
+}
+
 #[derive(PartialEq, Eq, Debug)]
 pub enum PingPongError {
     AlreadyPinged,                 // 0
@@ -14,6 +78,15 @@ pub enum PingPongError {
     DurationCannotBeZero,          // 5
     PingAmountCannotBeZero,        // 6
     OnlyOwnerCanPerformThisAction, // 7
+    OnlyAdminCanPerformThisAction, // 10
+    CannotRemoveOwner,             // 11
+    ProposalNotFound,              // 12
+    ProposalAlreadyExecuted,       // 13
+    MustHaveActivePingToVote,      // 14
+    AlreadyVoted,                  // 15
+    VotingPeriodEnded,             // 16
+    VotingStillOpen,               // 17
+    QuorumNotReached,              // 18
 }
 
 impl From<PingPongError> for SCError {
@@ -27,6 +100,15 @@ impl From<PingPongError> for SCError {
             PingPongError::DurationCannotBeZero => SCError::Custom(5),
             PingPongError::PingAmountCannotBeZero => SCError::Custom(6),
             PingPongError::OnlyOwnerCanPerformThisAction => SCError::Custom(7),
+            PingPongError::OnlyAdminCanPerformThisAction => SCError::Custom(10),
+            PingPongError::CannotRemoveOwner => SCError::Custom(11),
+            PingPongError::ProposalNotFound => SCError::Custom(12),
+            PingPongError::ProposalAlreadyExecuted => SCError::Custom(13),
+            PingPongError::MustHaveActivePingToVote => SCError::Custom(14),
+            PingPongError::AlreadyVoted => SCError::Custom(15),
+            PingPongError::VotingPeriodEnded => SCError::Custom(16),
+            PingPongError::VotingStillOpen => SCError::Custom(17),
+            PingPongError::QuorumNotReached => SCError::Custom(18),
         }
     }
 }
@@ -43,15 +125,51 @@ pub trait PingPong {
     #[storage_mapper("durationInSeconds")]
     fn duration_in_seconds(&self) -> SingleValueMapper<u64>;
 
+    #[storage_mapper("baseAmount")]
+    fn base_amount(&self) -> SingleValueMapper<BigUint>;
+
+    #[storage_mapper("slope")]
+    fn slope(&self) -> SingleValueMapper<BigUint>;
+
+    #[storage_mapper("pricingMode")]
+    fn pricing_mode(&self) -> SingleValueMapper<PricingMode>;
+
+    #[storage_mapper("userPaidAmount")]
+    fn user_paid_amount(&self, address: &ManagedAddress) -> SingleValueMapper<BigUint>;
+
+    #[storage_mapper("totalLocked")]
+    fn total_locked(&self) -> SingleValueMapper<BigUint>;
+
+    #[storage_mapper("governanceProposals")]
+    fn governance_proposals(&self) -> MapMapper<u64, GovernanceProposal<Self::Api>>;
+
+    #[storage_mapper("proposalIdCounter")]
+    fn proposal_id_counter(&self) -> SingleValueMapper<u64>;
+
+    #[storage_mapper("proposalVoters")]
+    fn proposal_voters(&self, proposal_id: u64) -> UnorderedSetMapper<ManagedAddress>;
+
+    #[storage_mapper("quorum")]
+    fn quorum(&self) -> SingleValueMapper<u64>;
+
+    #[storage_mapper("votingPeriodSeconds")]
+    fn voting_period_seconds(&self) -> SingleValueMapper<u64>;
+
     #[storage_mapper("userPingTimestamp")]
     fn user_ping_timestamp(&self, address: &ManagedAddress) -> SingleValueMapper<u64>;
 
+    #[storage_mapper("pingingUsers")]
+    fn pinging_users(&self) -> UnorderedSetMapper<ManagedAddress>;
+
     #[storage_mapper("paused")]
     fn paused(&self) -> SingleValueMapper<bool>;
 
     #[storage_mapper("owner")]
     fn owner(&self) -> SingleValueMapper<ManagedAddress>;
 
+    #[storage_mapper("admins")]
+    fn admins(&self) -> UnorderedSetMapper<ManagedAddress>;
+
 
     #[event("pongEvent")]
     fn pong_event(&self, #[indexed] user: &ManagedAddress);
@@ -59,11 +177,36 @@ pub trait PingPong {
     #[event("pingEvent")]
     fn ping_event(&self, #[indexed] user: &ManagedAddress);
 
+    #[event("proposalCreated")]
+    fn proposal_created_event(
+        &self,
+        #[indexed] proposal_id: u64,
+        #[indexed] proposer: &ManagedAddress,
+        setting: ParameterSetting,
+        new_value: &BigUint,
+    );
+
+    #[event("voteCast")]
+    fn vote_cast_event(
+        &self,
+        #[indexed] proposal_id: u64,
+        #[indexed] voter: &ManagedAddress,
+        approve: bool,
+    );
+
+    #[event("proposalExecuted")]
+    fn proposal_executed_event(&self, #[indexed] proposal_id: u64);
+
     #[init]
     fn init(
         &self,
         ping_amount: BigUint,
         duration_in_seconds: u64,
+        base_amount: BigUint,
+        slope: BigUint,
+        pricing_mode: PricingMode,
+        quorum: u64,
+        voting_period_seconds: u64,
         opt_token_id: OptionalValue<EgldOrEsdtTokenIdentifier>,
     ) {
         require!(ping_amount > 0, PingPongError::PingAmountCannotBeZero);
@@ -72,6 +215,14 @@ pub trait PingPong {
         require!(duration_in_seconds > 0, PingPongError::DurationCannotBeZero);
         self.duration_in_seconds().set(duration_in_seconds);
 
+        self.base_amount().set(&base_amount);
+        self.slope().set(&slope);
+        self.pricing_mode().set(pricing_mode);
+
+        require!(quorum > 0, SCError::Custom(20)); // "Quorum cannot be zero"
+        self.quorum().set(quorum);
+        self.voting_period_seconds().set(voting_period_seconds);
+
         let token_id = match opt_token_id {
             OptionalValue::Some(t) => t,
             OptionalValue::None => EgldOrEsdtTokenIdentifier::egld(),
@@ -80,17 +231,21 @@ pub trait PingPong {
 
         let caller = self.blockchain().get_caller();
         self.owner().set(&caller);
+        self.admins().insert(caller);
 
         self.paused().set(false);
     }
 
     #[upgrade]
-    fn upgrade(&self, ping_amount: BigUint, duration_in_seconds: u64) {
-        let caller = self.blockchain().get_caller();
-        require!(
-            caller == self.owner().get(),
-            PingPongError::OnlyOwnerCanPerformThisAction
-        );
+    fn upgrade(
+        &self,
+        ping_amount: BigUint,
+        duration_in_seconds: u64,
+        base_amount: BigUint,
+        slope: BigUint,
+        pricing_mode: PricingMode,
+    ) {
+        self.require_admin();
 
         // Yeni ping miktarını ve süreyi ayarlayın
         require!(ping_amount > 0, PingPongError::PingAmountCannotBeZero);
@@ -98,6 +253,10 @@ pub trait PingPong {
 
         require!(duration_in_seconds > 0, PingPongError::DurationCannotBeZero);
         self.duration_in_seconds().set(duration_in_seconds);
+
+        self.base_amount().set(&base_amount);
+        self.slope().set(&slope);
+        self.pricing_mode().set(pricing_mode);
     }
 
     #[payable("*")]
@@ -111,7 +270,7 @@ pub trait PingPong {
             PingPongError::InvalidPaymentToken
         );
         require!(
-            payment_amount == self.ping_amount().get(),
+            payment_amount == self.get_current_ping_price(),
             PingPongError::IncorrectPingAmount
         );
 
@@ -121,6 +280,9 @@ pub trait PingPong {
         let current_block_timestamp = self.blockchain().get_block_timestamp();
         self.user_ping_timestamp(&caller)
             .set(current_block_timestamp);
+        self.user_paid_amount(&caller).set(&payment_amount);
+        self.total_locked().update(|locked| *locked += &payment_amount);
+        self.pinging_users().insert(caller.clone());
 
 
         self.ping_event(&caller);
@@ -142,34 +304,208 @@ pub trait PingPong {
         );
 
         self.user_ping_timestamp(&caller).clear();
+        self.pinging_users().swap_remove(&caller);
 
         let token_id = self.accepted_payment_token_id().get();
-        let amount = self.ping_amount().get();
+        let amount = self.user_paid_amount(&caller).take();
+        self.total_locked().update(|locked| *locked -= &amount);
 
         self.send().direct(&caller, &token_id, 0, &amount);
         self.pong_event(&caller);
     }
 
 
-    #[endpoint]
-    fn pause(&self) {
+    /// Refunds every user whose ping deadline has passed, in a single owner call.
+    ///
+    /// The contract must be paused for the duration of a `pong_all` (resume)
+    /// sequence: `ping` and `pong` both refuse while paused, so the participant
+    /// set cannot grow or be re-ordered between resume calls. That lets each call
+    /// re-scan from the front instead of trusting a persisted index, which a
+    /// concurrent `swap_remove` could otherwise invalidate. The loop checks the
+    /// remaining gas before every refund; when it drops below
+    /// `GAS_PER_PONG_ALL_ITERATION` it returns `InterruptedBeforeOutOfGas` and the
+    /// operator resumes by calling `pong_all` again, otherwise `Completed`.
+    #[endpoint(pongAll)]
+    fn pong_all(&self) -> OperationCompletionStatus {
         let caller = self.blockchain().get_caller();
         require!(
             caller == self.owner().get(),
             PingPongError::OnlyOwnerCanPerformThisAction
         );
+        require!(self.paused().get(), SCError::Custom(19)); // "Contract must be paused"
+
+        let token_id = self.accepted_payment_token_id().get();
+        let duration_in_seconds = self.duration_in_seconds().get();
+        let current_timestamp = self.blockchain().get_block_timestamp();
+
+        let mut users = self.pinging_users();
+        let mut index = 1;
+        while index <= users.len() {
+            if self.blockchain().get_gas_left() < GAS_PER_PONG_ALL_ITERATION {
+                return OperationCompletionStatus::InterruptedBeforeOutOfGas;
+            }
+
+            let user = users.get_by_index(index);
+            let pong_enable_timestamp = self.user_ping_timestamp(&user).get() + duration_in_seconds;
+            if current_timestamp < pong_enable_timestamp {
+                index += 1;
+                continue;
+            }
+
+            self.user_ping_timestamp(&user).clear();
+            let amount = self.user_paid_amount(&user).take();
+            self.total_locked().update(|locked| *locked -= &amount);
+            // `swap_remove` moves the last element into `index`, so the index is
+            // not advanced — the swapped-in user is processed on the next pass.
+            users.swap_remove(&user);
+            self.send().direct(&user, &token_id, 0, &amount);
+            self.pong_event(&user);
+        }
+
+        OperationCompletionStatus::Completed
+    }
+
+
+    #[endpoint]
+    fn pause(&self) {
+        self.require_admin();
         self.paused().set(true);
     }
 
 
     #[endpoint]
     fn unpause(&self) {
+        self.require_admin();
+        self.paused().set(false);
+    }
+
+    #[endpoint(addAdmin)]
+    fn add_admin(&self, address: ManagedAddress) {
+        self.require_admin();
+        self.admins().insert(address);
+    }
+
+    #[endpoint(removeAdmin)]
+    fn remove_admin(&self, address: ManagedAddress) {
+        self.require_admin();
+        // The owner is the super-admin and can never be removed, which keeps the
+        // set from ever being emptied.
+        require!(
+            address != self.owner().get(),
+            PingPongError::CannotRemoveOwner
+        );
+        self.admins().swap_remove(&address);
+    }
+
+    #[endpoint(proposeParameterChange)]
+    fn propose_parameter_change(&self, setting: ParameterSetting, new_value: BigUint) -> u64 {
+        self.require_admin();
+
+        let proposal_id = self.proposal_id_counter().get() + 1;
+        self.proposal_id_counter().set(proposal_id);
+
+        let proposer = self.blockchain().get_caller();
+        let proposal = GovernanceProposal {
+            proposer: proposer.clone(),
+            setting,
+            new_value: new_value.clone(),
+            creation_timestamp: self.blockchain().get_block_timestamp(),
+            yes_votes: 0,
+            no_votes: 0,
+            executed: false,
+        };
+        self.governance_proposals().insert(proposal_id, proposal);
+
+        self.proposal_created_event(proposal_id, &proposer, setting, &new_value);
+        proposal_id
+    }
+
+    #[endpoint(vote)]
+    fn vote(&self, proposal_id: u64, approve: bool) {
         let caller = self.blockchain().get_caller();
         require!(
-            caller == self.owner().get(),
-            PingPongError::OnlyOwnerCanPerformThisAction
+            self.did_user_ping(&caller),
+            PingPongError::MustHaveActivePingToVote
         );
-        self.paused().set(false);
+
+        require!(
+            self.governance_proposals().contains_key(&proposal_id),
+            PingPongError::ProposalNotFound
+        );
+        let mut proposal = self.governance_proposals().get(&proposal_id).unwrap();
+        require!(!proposal.executed, PingPongError::ProposalAlreadyExecuted);
+
+        let current_timestamp = self.blockchain().get_block_timestamp();
+        require!(
+            voting_open(
+                current_timestamp,
+                proposal.creation_timestamp,
+                self.voting_period_seconds().get()
+            ),
+            PingPongError::VotingPeriodEnded
+        );
+
+        require!(
+            self.proposal_voters(proposal_id).insert(caller.clone()),
+            PingPongError::AlreadyVoted
+        );
+
+        if approve {
+            proposal.yes_votes += 1;
+        } else {
+            proposal.no_votes += 1;
+        }
+        self.governance_proposals().insert(proposal_id, proposal);
+
+        self.vote_cast_event(proposal_id, &caller, approve);
+    }
+
+    #[endpoint(executeProposal)]
+    fn execute_proposal(&self, proposal_id: u64) {
+        require!(
+            self.governance_proposals().contains_key(&proposal_id),
+            PingPongError::ProposalNotFound
+        );
+        let mut proposal = self.governance_proposals().get(&proposal_id).unwrap();
+        require!(!proposal.executed, PingPongError::ProposalAlreadyExecuted);
+
+        let current_timestamp = self.blockchain().get_block_timestamp();
+        require!(
+            !voting_open(
+                current_timestamp,
+                proposal.creation_timestamp,
+                self.voting_period_seconds().get()
+            ),
+            PingPongError::VotingStillOpen
+        );
+        require!(
+            proposal_passes(
+                proposal.yes_votes,
+                proposal.no_votes,
+                self.quorum().get()
+            ),
+            PingPongError::QuorumNotReached
+        );
+
+        match proposal.setting {
+            ParameterSetting::PingAmount => {
+                require!(
+                    proposal.new_value > 0,
+                    PingPongError::PingAmountCannotBeZero
+                );
+                self.ping_amount().set(&proposal.new_value);
+            }
+            ParameterSetting::DurationInSeconds => {
+                let duration = proposal.new_value.to_u64().unwrap_or_default();
+                require!(duration > 0, PingPongError::DurationCannotBeZero);
+                self.duration_in_seconds().set(duration);
+            }
+        }
+
+        proposal.executed = true;
+        self.governance_proposals().insert(proposal_id, proposal);
+
+        self.proposal_executed_event(proposal_id);
     }
 
     #[endpoint]
@@ -224,6 +560,66 @@ pub trait PingPong {
     }
 
 
+    fn require_admin(&self) {
+        let caller = self.blockchain().get_caller();
+        require!(
+            self.admins().contains(&caller),
+            PingPongError::OnlyAdminCanPerformThisAction
+        );
+    }
+
+    #[view(isAdmin)]
+    fn is_admin(&self, address: &ManagedAddress) -> bool {
+        self.admins().contains(address)
+    }
+
+    #[view(getAdmins)]
+    fn get_admins(&self) -> MultiValueEncoded<ManagedAddress> {
+        let mut result = MultiValueEncoded::new();
+        for admin in self.admins().iter() {
+            result.push(admin);
+        }
+        result
+    }
+
+    #[view(getProposal)]
+    fn get_proposal(&self, proposal_id: u64) -> GovernanceProposal<Self::Api> {
+        require!(
+            self.governance_proposals().contains_key(&proposal_id),
+            PingPongError::ProposalNotFound
+        );
+        self.governance_proposals().get(&proposal_id).unwrap()
+    }
+
+    #[view(getProposalCount)]
+    fn get_proposal_count(&self) -> u64 {
+        self.proposal_id_counter().get()
+    }
+
+    #[view(getUserStatus)]
+    fn get_user_status(&self, address: &ManagedAddress) -> UserStatus {
+        if !self.did_user_ping(address) {
+            return UserStatus::Unknown;
+        }
+
+        let pong_enable_timestamp = self.get_pong_enable_timestamp(address);
+        let current_timestamp = self.blockchain().get_block_timestamp();
+        if current_timestamp >= pong_enable_timestamp {
+            UserStatus::PingedReady
+        } else {
+            UserStatus::PingedWaiting
+        }
+    }
+
+    #[view(getContractState)]
+    fn get_contract_state(&self) -> ContractState {
+        if self.paused().get() {
+            ContractState::Paused
+        } else {
+            ContractState::Active
+        }
+    }
+
     #[view(getAcceptedPaymentToken)]
     fn get_accepted_payment_token(&self) -> EgldOrEsdtTokenIdentifier {
         self.accepted_payment_token_id().get()
@@ -234,6 +630,22 @@ pub trait PingPong {
         self.ping_amount().get()
     }
 
+    #[view(getCurrentPingPrice)]
+    fn get_current_ping_price(&self) -> BigUint {
+        match self.pricing_mode().get() {
+            PricingMode::Fixed => self.ping_amount().get(),
+            PricingMode::Linear => {
+                let active_count = self.pinging_users().len();
+                self.base_amount().get() + self.slope().get() * active_count as u64
+            }
+        }
+    }
+
+    #[view(getTotalLockedFunds)]
+    fn get_total_locked_funds(&self) -> BigUint {
+        self.total_locked().get()
+    }
+
     #[view(getDurationTimestamp)]
     fn get_duration_timestamp(&self) -> u64 {
         self.duration_in_seconds().get()
@@ -256,3 +668,66 @@ pub trait PingPong {
         self.owner().get()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{proposal_passes, voting_open};
+    extern crate std;
+    use std::vec::Vec;
+
+    /// Pure model of the `pong_all` index/`swap_remove` walk (0-based here, where
+    /// the contract's set is 1-based): on each refund move the last element into
+    /// the freed slot without advancing the index. Returns the ids refunded, in
+    /// visit order.
+    fn simulate_pong_all(ready: &[bool]) -> Vec<usize> {
+        let mut items: Vec<(usize, bool)> = ready.iter().copied().enumerate().collect();
+        let mut refunded = Vec::new();
+        let mut index = 0;
+        while index < items.len() {
+            let (id, is_ready) = items[index];
+            if !is_ready {
+                index += 1;
+                continue;
+            }
+            refunded.push(id);
+            let last = items.len() - 1;
+            items.swap(index, last);
+            items.pop();
+            // index is not advanced: the swapped-in element now occupies it
+        }
+        refunded
+    }
+
+    #[test]
+    fn pong_all_walk_refunds_every_ready_user_once() {
+        let ready = [true, false, true, true, false];
+        let mut refunded = simulate_pong_all(&ready);
+        refunded.sort_unstable();
+        assert_eq!(refunded, std::vec![0, 2, 3]);
+    }
+
+    #[test]
+    fn pong_all_walk_handles_all_ready_and_none_ready() {
+        assert_eq!(simulate_pong_all(&[true, true, true]).len(), 3);
+        assert!(simulate_pong_all(&[false, false]).is_empty());
+        assert!(simulate_pong_all(&[]).is_empty());
+    }
+
+    #[test]
+    fn voting_open_boundary() {
+        // Open strictly before creation + period, closed exactly on the edge.
+        assert!(voting_open(0, 0, 100));
+        assert!(voting_open(99, 0, 100));
+        assert!(!voting_open(100, 0, 100));
+        assert!(!voting_open(101, 0, 100));
+    }
+
+    #[test]
+    fn proposal_passes_requires_quorum_and_majority() {
+        // Needs to beat both the quorum and the no tally.
+        assert!(proposal_passes(6, 5, 3));
+        assert!(!proposal_passes(4, 0, 5)); // below quorum
+        assert!(!proposal_passes(5, 5, 3)); // ties the no votes
+        assert!(!proposal_passes(1, 5, 0)); // zero quorum cannot rescue a minority
+    }
+}